@@ -0,0 +1,221 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::extract::FromRequestParts;
+use http::{request::Parts, HeaderMap};
+
+use super::ServerState;
+
+/// The base URL to use for the current request, as an axum extractor.
+///
+/// This is [`ServerState::base_url`] by default. If
+/// [`trust_forwarded_headers`](super::ServerOptions::trust_forwarded_headers) is enabled on the
+/// server, it is instead reconstructed per-request from the `Forwarded` header (or, failing
+/// that, `X-Forwarded-Proto`/`X-Forwarded-Host`/`X-Forwarded-Port`), so handlers generate correct
+/// absolute URLs (e.g. for redirects or `hx-push-url`) when running behind a reverse proxy.
+#[derive(Debug, Clone)]
+pub struct BaseUrl(pub http::Uri);
+
+impl FromRequestParts<Arc<ServerState>> for BaseUrl {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<ServerState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self(state.resolve_base_url(&parts.headers)))
+    }
+}
+
+/// Reconstruct a base URL from forwarding-related request headers, preferring the standardized
+/// `Forwarded` header (RFC 7239) over the more common but non-standard `X-Forwarded-*` ones.
+///
+/// Returns `None` if none of these headers are present or parseable.
+pub(super) fn base_url_from_headers(headers: &HeaderMap) -> Option<http::Uri> {
+    from_forwarded_header(headers).or_else(|| from_x_forwarded_headers(headers))
+}
+
+fn from_forwarded_header(headers: &HeaderMap) -> Option<http::Uri> {
+    let value = header_str(headers, http::header::FORWARDED.as_str())?;
+    // Only the first hop (the one closest to the client) is relevant to us.
+    let first_hop = value.split(',').next()?;
+
+    let mut proto = None;
+    let mut host = None;
+
+    for pair in first_hop.split(';') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+
+        let value = value.trim().trim_matches('"');
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "proto" => proto = Some(value),
+            "host" => host = Some(value),
+            _ => {}
+        }
+    }
+
+    build_uri(proto.unwrap_or("http"), host?)
+}
+
+fn from_x_forwarded_headers(headers: &HeaderMap) -> Option<http::Uri> {
+    let host = first_forwarded_value(headers, "x-forwarded-host")?;
+    let scheme = first_forwarded_value(headers, "x-forwarded-proto").unwrap_or("http");
+    let port = first_forwarded_value(headers, "x-forwarded-port");
+
+    let authority = match port {
+        Some(port) if !host_has_port(host) => format!("{host}:{port}"),
+        _ => host.to_owned(),
+    };
+
+    build_uri(scheme, &authority)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// Get the value of an `X-Forwarded-*` header, keeping only the first entry of what may be a
+/// comma-separated chain built up by multiple proxies (the entry closest to the client, same as
+/// the first hop of the `Forwarded` header).
+fn first_forwarded_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    let value = header_str(headers, name)?;
+
+    Some(value.split(',').next()?.trim())
+}
+
+fn host_has_port(host: &str) -> bool {
+    host.rsplit_once(':')
+        .is_some_and(|(_, port)| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn build_uri(scheme: &str, authority: &str) -> Option<http::Uri> {
+    format!("{scheme}://{authority}").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in pairs {
+            headers.insert(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                http::HeaderValue::from_str(value).unwrap(),
+            );
+        }
+
+        headers
+    }
+
+    #[test]
+    fn forwarded_header_takes_priority_over_x_forwarded_headers() {
+        let headers = headers(&[
+            ("forwarded", "proto=https;host=example.com"),
+            ("x-forwarded-proto", "http"),
+            ("x-forwarded-host", "other.example.com"),
+        ]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn forwarded_header_uses_only_the_first_hop() {
+        let headers = headers(&[(
+            "forwarded",
+            "proto=https;host=example.com, proto=http;host=internal",
+        )]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn forwarded_header_handles_quoted_values() {
+        let headers = headers(&[("forwarded", "proto=\"https\";host=\"example.com\"")]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn forwarded_header_defaults_to_http_without_proto() {
+        let headers = headers(&[("forwarded", "host=example.com")]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "http://example.com"
+        );
+    }
+
+    #[test]
+    fn x_forwarded_headers_are_used_without_a_forwarded_header() {
+        let headers = headers(&[
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn x_forwarded_headers_append_port_when_host_has_none() {
+        let headers = headers(&[
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "example.com"),
+            ("x-forwarded-port", "8443"),
+        ]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com:8443"
+        );
+    }
+
+    #[test]
+    fn x_forwarded_headers_do_not_append_port_when_host_already_has_one() {
+        let headers = headers(&[
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "example.com:9000"),
+            ("x-forwarded-port", "8443"),
+        ]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com:9000"
+        );
+    }
+
+    #[test]
+    fn x_forwarded_headers_take_the_first_hop_of_each_comma_separated_chain() {
+        let headers = headers(&[
+            ("x-forwarded-proto", "https, http"),
+            ("x-forwarded-host", "example.com, internal"),
+        ]);
+
+        assert_eq!(
+            base_url_from_headers(&headers).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn no_headers_resolve_to_nothing() {
+        let headers = headers(&[]);
+
+        assert!(base_url_from_headers(&headers).is_none());
+    }
+}
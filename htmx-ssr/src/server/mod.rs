@@ -1,8 +1,22 @@
 use std::{future::Future, pin::Pin, sync::Arc};
 
+mod forwarded;
+#[cfg(feature = "reloadable")]
+mod reload;
+#[cfg(feature = "static-assets")]
+mod static_assets;
 mod state;
-
+#[cfg(feature = "tls")]
+mod tls;
+
+pub use forwarded::BaseUrl;
+#[cfg(feature = "reloadable")]
+pub use reload::{ReloadHandle, ReloadableServer};
+#[cfg(feature = "static-assets")]
+pub use static_assets::{LoadStaticAssetsError, StaticAssets};
 pub use state::ServerState;
+#[cfg(feature = "tls")]
+pub use tls::{RustlsConfig, RustlsConfigFromPemFileError};
 
 /// The options for the server.
 #[derive(Debug, Clone, Default)]
@@ -18,6 +32,17 @@ pub struct ServerOptions {
     /// If `HTMX_SSR_BASE_URL` is set in the environment, it will be read and used as the base URL
     /// when calling `ServerOptions::from_env`.
     pub base_url: Option<http::Uri>,
+
+    /// Whether to trust `Forwarded`/`X-Forwarded-*` request headers to reconstruct the base URL
+    /// on a per-request basis, instead of always using `base_url`.
+    ///
+    /// Only enable this if the server is only reachable through a trusted reverse proxy that
+    /// sets (and overwrites) these headers itself: otherwise, a client can spoof them to make
+    /// handlers generate arbitrary absolute URLs.
+    ///
+    /// If `HTMX_SSR_TRUST_FORWARDED_HEADERS` is set to `1` or `true` in the environment, this
+    /// will be enabled when calling `ServerOptions::from_env`.
+    pub trust_forwarded_headers: bool,
 }
 
 /// An error that can occur when trying to get the server options from the environment.
@@ -49,6 +74,10 @@ impl ServerOptions {
     /// The environment variable name for the base URL.
     pub const HTMX_SSR_BASE_URL: &'static str = "HTMX_SSR_BASE_URL";
 
+    /// The environment variable name for `trust_forwarded_headers`.
+    pub const HTMX_SSR_TRUST_FORWARDED_HEADERS: &'static str =
+        "HTMX_SSR_TRUST_FORWARDED_HEADERS";
+
     fn env_var(name: &'static str) -> Result<Option<String>, ServerOptionsFromEnvError> {
         match std::env::var(name) {
             Ok(value) => Ok(if value.is_empty() { None } else { Some(value) }),
@@ -89,7 +118,21 @@ impl ServerOptions {
             }
         };
 
-        Ok(Self { base_url })
+        let trust_forwarded_headers = Self::env_var(Self::HTMX_SSR_TRUST_FORWARDED_HEADERS)?
+            .is_some_and(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+
+        if trust_forwarded_headers {
+            tracing::info!(
+                "{} was set: trusting `Forwarded`/`X-Forwarded-*` request headers to determine \
+                 the base URL. Only do this behind a trusted reverse proxy.",
+                Self::HTMX_SSR_TRUST_FORWARDED_HEADERS
+            );
+        }
+
+        Ok(Self {
+            base_url,
+            trust_forwarded_headers,
+        })
     }
 }
 
@@ -111,6 +154,18 @@ pub struct Server {
 
     /// The options for the server.
     options: ServerOptions,
+
+    /// The maximum amount of time to wait for in-flight connections to drain after the graceful
+    /// shutdown signal fires, before returning anyway.
+    shutdown_timeout: Option<std::time::Duration>,
+
+    /// The TLS configuration to terminate TLS in-process, if any.
+    #[cfg(feature = "tls")]
+    tls_config: Option<RustlsConfig>,
+
+    /// The mount path and registry of static assets to serve, if any.
+    #[cfg(feature = "static-assets")]
+    static_assets: Option<(String, StaticAssets)>,
 }
 
 /// An error that can occur when instantiating a new HTMX-SSR server with auto-reload features.
@@ -132,6 +187,26 @@ pub enum ServeError {
     /// An error occurred while trying to get the local address of the listener.
     #[error("failed to get the local address of the listener: {0}")]
     LocalAddr(std::io::Error),
+
+    /// [`Server::into_reloadable`] was called on a server configured with
+    /// [`Server::with_rustls_config`].
+    #[cfg(feature = "tls")]
+    #[error(
+        "TLS is not supported in reloadable mode; call `into_reloadable` on a server without a \
+         rustls config, or terminate TLS upstream of it"
+    )]
+    TlsUnsupportedInReloadableMode,
+}
+
+/// A [`Server`] that has been bound to its final, stateful router, ready to be served either
+/// directly (`serve`) or in `reloadable` mode (`into_reloadable`).
+struct PreparedServer {
+    listener: tokio::net::TcpListener,
+    router: axum::Router,
+    graceful_shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    shutdown_timeout: Option<std::time::Duration>,
+    #[cfg(feature = "tls")]
+    tls_config: Option<RustlsConfig>,
 }
 
 impl Server {
@@ -146,6 +221,11 @@ impl Server {
             router,
             graceful_shutdown,
             options,
+            shutdown_timeout: None,
+            #[cfg(feature = "tls")]
+            tls_config: None,
+            #[cfg(feature = "static-assets")]
+            static_assets: None,
         }
     }
 
@@ -180,6 +260,27 @@ impl Server {
         Ok(self)
     }
 
+    /// Set the `rustls` configuration to use to terminate TLS in-process.
+    ///
+    /// When set, `serve` terminates TLS on every accepted connection before handing it to the
+    /// router, so the server can run without a reverse proxy in front of it. This also changes
+    /// the default scheme used to guess [`ServerState::base_url`] to `https`.
+    #[cfg(feature = "tls")]
+    pub fn with_rustls_config(mut self, config: RustlsConfig) -> Self {
+        self.tls_config = Some(config);
+        self
+    }
+
+    /// Mount a registry of static assets under `mount_path` (e.g. `/assets`).
+    ///
+    /// Fingerprinted asset URLs can then be generated from handlers via
+    /// [`ServerState::asset_path`].
+    #[cfg(feature = "static-assets")]
+    pub fn with_static_assets(mut self, mount_path: impl Into<String>, assets: StaticAssets) -> Self {
+        self.static_assets = Some((mount_path.into(), assets));
+        self
+    }
+
     /// Instantiate a new HTMX-SSR server with all the auto-reload features enabled.
     ///
     /// Attempts to get a TCP listener from the environment if run through `listenfd`, falling
@@ -218,23 +319,242 @@ impl Server {
         })
     }
 
-    /// Serve the application.
-    pub async fn serve(self) -> Result<(), ServeError> {
+    /// Set the graceful shutdown signal to fire on either `SIGINT` or `SIGTERM` (falling back to
+    /// `ctrl-c` on platforms without Unix signals), so that containerized/systemd deployments
+    /// sending `SIGTERM` shut down gracefully rather than being killed outright.
+    pub fn with_unix_signals_graceful_shutdown(self) -> Self {
+        self.with_graceful_shutdown(async move {
+            tracing::info!("Listening for `SIGINT`/`SIGTERM` for graceful shutdown...");
+
+            #[cfg(unix)]
+            {
+                match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                    Ok(mut sigterm) => {
+                        tokio::select! {
+                            _ = tokio::signal::ctrl_c() => {
+                                tracing::info!("Received `SIGINT`, shutting down gracefully.");
+                            }
+                            _ = sigterm.recv() => {
+                                tracing::info!("Received `SIGTERM`, shutting down gracefully.");
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        // Still wire up `ctrl-c` rather than hanging forever: a deployment that
+                        // can't register for `SIGTERM` should still be able to shut down on
+                        // `SIGINT`.
+                        tracing::error!(
+                            "Failed to register for `SIGTERM`: {err}; falling back to `ctrl-c` only"
+                        );
+
+                        if let Err(err) = tokio::signal::ctrl_c().await {
+                            tracing::error!("Failed to register for `ctrl-c` signal: {err}");
+                        }
+
+                        tracing::info!("Received `ctrl-c` signal, shutting down gracefully.");
+                    }
+                }
+            }
+
+            #[cfg(not(unix))]
+            {
+                if let Err(err) = tokio::signal::ctrl_c().await {
+                    tracing::error!("Failed to register for `ctrl-c` signal: {err}");
+                }
+
+                tracing::info!("Received `ctrl-c` signal, shutting down gracefully.");
+            }
+        })
+    }
+
+    /// Set a deadline for in-flight requests to drain after the graceful shutdown signal fires.
+    ///
+    /// Once the signal fires, `serve` starts Axum's graceful shutdown and races it against this
+    /// timeout: if in-flight connections haven't drained by the time it elapses, a warning is
+    /// logged and `serve` returns anyway, leaving it to the caller (or the process supervisor) to
+    /// terminate any connections that are still open.
+    pub fn with_shutdown_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.shutdown_timeout = Some(timeout);
+        self
+    }
+
+    /// Turn this server into a [`ReloadableServer`], returning it along with a [`ReloadHandle`]
+    /// that can be used to hot-swap its router at any time.
+    ///
+    /// Unlike `serve`, which binds a single router for the whole lifetime of the call, a
+    /// `ReloadableServer` keeps the same TCP listener open across router rebuilds: in-flight
+    /// connections keep being served by the router they were accepted under, so reloading never
+    /// drops a connection and never risks an `EADDRINUSE` race from rebinding the socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServeError::TlsUnsupportedInReloadableMode`] if this server was configured with
+    /// [`with_rustls_config`](Self::with_rustls_config): TLS termination isn't implemented for the
+    /// manual accept loop `ReloadableServer` runs, and silently downgrading to plain TCP would
+    /// make `ServerState::base_url` lie about the scheme.
+    #[cfg(feature = "reloadable")]
+    pub fn into_reloadable(self) -> Result<(ReloadHandle, ReloadableServer), ServeError> {
+        #[cfg(feature = "tls")]
+        if self.tls_config.is_some() {
+            return Err(ServeError::TlsUnsupportedInReloadableMode);
+        }
+
+        let prepared = self.prepare()?;
+
+        let current = Arc::new(arc_swap::ArcSwap::from_pointee(prepared.router));
+        let handle = ReloadHandle {
+            current: current.clone(),
+        };
+        let server = ReloadableServer {
+            listener: prepared.listener,
+            current,
+            graceful_shutdown: prepared.graceful_shutdown,
+            shutdown_timeout: prepared.shutdown_timeout,
+        };
+
+        Ok((handle, server))
+    }
+
+    /// Assemble the final, stateful router (nesting static assets and attaching [`ServerState`])
+    /// and split `self` into the pieces that `serve` and `into_reloadable` each dispatch on their
+    /// own way.
+    fn prepare(self) -> Result<PreparedServer, ServeError> {
         let local_addr = self.listener.local_addr().map_err(ServeError::LocalAddr)?;
 
         tracing::info!("HTMX SSR server listening on TCP/{local_addr}.");
 
-        let state = ServerState::new(self.options, local_addr);
+        #[cfg(feature = "tls")]
+        let is_tls = self.tls_config.is_some();
+        #[cfg(not(feature = "tls"))]
+        let is_tls = false;
+
+        let mut router = self.router;
+
+        #[cfg(feature = "static-assets")]
+        if let Some((mount_path, _)) = &self.static_assets {
+            router = router.nest(mount_path, static_assets::StaticAssets::router());
+        }
+
+        let state = ServerState::new(
+            self.options,
+            local_addr,
+            is_tls,
+            #[cfg(feature = "static-assets")]
+            self.static_assets,
+        );
 
         tracing::info!("Now serving HTMX SSR server at `{}`...", state.base_url);
 
-        let router = self.router.with_state(Arc::new(state));
-        let serve = axum::serve(self.listener, router);
+        let router = router.with_state(Arc::new(state));
+
+        Ok(PreparedServer {
+            listener: self.listener,
+            router,
+            graceful_shutdown: self.graceful_shutdown,
+            shutdown_timeout: self.shutdown_timeout,
+            #[cfg(feature = "tls")]
+            tls_config: self.tls_config,
+        })
+    }
+
+    /// Serve the application.
+    pub async fn serve(self) -> Result<(), ServeError> {
+        let prepared = self.prepare()?;
+
+        #[cfg(feature = "tls")]
+        if let Some(tls_config) = prepared.tls_config {
+            tracing::info!("TLS is enabled: terminating TLS in-process with `rustls`.");
+
+            let listener = tls::TlsListener::new(prepared.listener, &tls_config);
+            let serve = axum::serve(listener, prepared.router);
+
+            return match prepared.graceful_shutdown {
+                Some(signal) => {
+                    let (signal, shutdown_started) = notify_on_fire(signal);
+
+                    run_graceful_shutdown(
+                        serve.with_graceful_shutdown(signal),
+                        shutdown_started,
+                        prepared.shutdown_timeout,
+                    )
+                    .await
+                }
+                None => serve.await,
+            }
+            .map_err(Into::into);
+        }
+
+        let serve = axum::serve(prepared.listener, prepared.router);
+
+        match prepared.graceful_shutdown {
+            Some(signal) => {
+                let (signal, shutdown_started) = notify_on_fire(signal);
 
-        match self.graceful_shutdown {
-            Some(signal) => serve.with_graceful_shutdown(signal).await,
+                run_graceful_shutdown(
+                    serve.with_graceful_shutdown(signal),
+                    shutdown_started,
+                    prepared.shutdown_timeout,
+                )
+                .await
+            }
             None => serve.await,
         }
         .map_err(Into::into)
     }
-}
\ No newline at end of file
+}
+
+/// Wrap a graceful shutdown signal so that, in addition to resolving itself, it notifies a
+/// [`tokio::sync::oneshot::Receiver`] the instant it fires.
+///
+/// This lets callers race the drain phase that follows against a deadline that only starts
+/// counting down once the signal actually fires, rather than from the moment the server started
+/// serving.
+fn notify_on_fire(
+    signal: Pin<Box<dyn Future<Output = ()> + Send>>,
+) -> (
+    impl Future<Output = ()> + Send + 'static,
+    tokio::sync::oneshot::Receiver<()>,
+) {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let signal = async move {
+        signal.await;
+        let _ = tx.send(());
+    };
+
+    (signal, rx)
+}
+
+/// Run a `serve.with_graceful_shutdown(signal)` future to completion, optionally bounding how
+/// long it may spend draining in-flight connections once `shutdown_started` fires.
+async fn run_graceful_shutdown(
+    serve: impl Future<Output = std::io::Result<()>>,
+    shutdown_started: tokio::sync::oneshot::Receiver<()>,
+    shutdown_timeout: Option<std::time::Duration>,
+) -> std::io::Result<()> {
+    let Some(shutdown_timeout) = shutdown_timeout else {
+        return serve.await;
+    };
+
+    let deadline = async move {
+        // If the sender was dropped without firing, there is nothing to bound: just wait
+        // forever, letting the `serve` branch of the `select!` below win.
+        if shutdown_started.await.is_ok() {
+            tokio::time::sleep(shutdown_timeout).await;
+        } else {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    tokio::select! {
+        result = serve => result,
+        () = deadline => {
+            tracing::warn!(
+                "Graceful shutdown grace period of {shutdown_timeout:?} elapsed before all \
+                 connections drained; returning anyway."
+            );
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+
+/// A fully-built, stateless router, ready to serve connections.
+///
+/// This is what a [`super::Router`] becomes once [`with_state`](axum::Router::with_state) has
+/// been called on it.
+type BoundRouter = axum::Router;
+
+/// A handle used to trigger an in-process hot-swap of the router served by a
+/// [`Server`](super::Server) running
+/// in [`reloadable`](super::Server::into_reloadable) mode.
+///
+/// Swapping the router never closes or rebinds the listening socket: connections already being
+/// served keep running against the router they were accepted under until they complete, while
+/// newly accepted connections are dispatched to whichever router was swapped in most recently.
+///
+/// This deliberately doesn't watch anything itself: deciding when to reload (a file-watcher on
+/// your template directory, a signal, an admin endpoint, ...) is left entirely to the caller.
+/// Wire up whichever trigger makes sense for your deployment and have it call
+/// [`reload`](Self::reload) with a freshly built router.
+#[derive(Clone)]
+pub struct ReloadHandle {
+    pub(super) current: Arc<ArcSwap<BoundRouter>>,
+}
+
+impl ReloadHandle {
+    /// Atomically swap in a freshly built router.
+    pub fn reload(&self, router: BoundRouter) {
+        tracing::info!("Hot-swapping the HTMX SSR router...");
+        self.current.store(Arc::new(router));
+    }
+}
+
+/// A [`Server`](super::Server) running in `reloadable` mode.
+///
+/// Unlike [`Server::serve`](super::Server::serve), which binds a single router for the lifetime
+/// of the call, this keeps the listening socket open across reloads (until the graceful-shutdown
+/// signal fires and [`serve`](Self::serve) returns) and serves
+/// every accepted connection against whichever router is current at accept time, so a
+/// [`ReloadHandle::reload`] call never drops a connection or requires rebinding the socket.
+pub struct ReloadableServer {
+    pub(super) listener: tokio::net::TcpListener,
+    pub(super) current: Arc<ArcSwap<BoundRouter>>,
+    pub(super) graceful_shutdown: Option<
+        std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>,
+    >,
+    pub(super) shutdown_timeout: Option<std::time::Duration>,
+}
+
+impl ReloadableServer {
+    /// Serve connections, dispatching each one to the router that is current at accept time.
+    ///
+    /// Once the graceful-shutdown signal fires, every in-flight connection (including idle
+    /// keep-alive ones) is asked to wind down via hyper's own `graceful_shutdown`, then awaited;
+    /// configure [`with_shutdown_timeout`](super::Server::with_shutdown_timeout) so a client that
+    /// never closes its end can't keep this call pending forever.
+    pub async fn serve(self) -> Result<(), super::ServeError> {
+        let Self {
+            listener,
+            current,
+            graceful_shutdown,
+            shutdown_timeout,
+        } = self;
+
+        let local_addr = listener
+            .local_addr()
+            .map_err(super::ServeError::LocalAddr)?;
+
+        tracing::info!("HTMX SSR server listening on TCP/{local_addr} in reloadable mode.");
+
+        let shutdown = async move {
+            match graceful_shutdown {
+                Some(signal) => signal.await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(shutdown);
+
+        let mut connections = tokio::task::JoinSet::new();
+        let (connection_shutdown_tx, connection_shutdown_rx) = tokio::sync::watch::channel(());
+
+        loop {
+            tokio::select! {
+                biased;
+
+                () = &mut shutdown => {
+                    tracing::info!(
+                        "Reloadable server shutting down gracefully; draining in-flight \
+                         connections..."
+                    );
+                    break;
+                }
+                accepted = listener.accept() => {
+                    let (stream, _addr) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(err) => {
+                            tracing::warn!("Failed to accept a TCP connection: {err}");
+                            continue;
+                        }
+                    };
+
+                    let router = current.load_full();
+                    let mut connection_shutdown_rx = connection_shutdown_rx.clone();
+
+                    connections.spawn(async move {
+                        let io = TokioIo::new(stream);
+                        let service = hyper_util::service::TowerToHyperService::new((*router).clone());
+
+                        let connection = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new())
+                            .serve_connection_with_upgrades(io, service);
+                        tokio::pin!(connection);
+
+                        loop {
+                            tokio::select! {
+                                result = connection.as_mut() => {
+                                    if let Err(err) = result {
+                                        tracing::warn!("Error serving a connection: {err}");
+                                    }
+                                    break;
+                                }
+                                _ = connection_shutdown_rx.changed() => {
+                                    connection.as_mut().graceful_shutdown();
+                                }
+                            }
+                        }
+                    });
+                }
+                // Reap finished connections as they complete; otherwise `JoinSet` keeps every
+                // `JoinHandle` around until it's joined, leaking memory for the lifetime of the
+                // server.
+                Some(_) = connections.join_next() => {}
+            }
+        }
+
+        // Ask every in-flight connection to stop accepting new requests (closing keep-alive
+        // connections once their current request, if any, completes) before draining them.
+        let _ = connection_shutdown_tx.send(());
+
+        drain_connections(connections, shutdown_timeout).await;
+
+        Ok(())
+    }
+}
+
+/// Wait for every in-flight connection task in `connections` to finish, optionally giving up
+/// after `shutdown_timeout` and returning anyway.
+async fn drain_connections(
+    mut connections: tokio::task::JoinSet<()>,
+    shutdown_timeout: Option<std::time::Duration>,
+) {
+    let drain_all = async {
+        while connections.join_next().await.is_some() {}
+    };
+
+    match shutdown_timeout {
+        Some(shutdown_timeout) => {
+            tokio::select! {
+                () = drain_all => {}
+                () = tokio::time::sleep(shutdown_timeout) => {
+                    tracing::warn!(
+                        "Graceful shutdown grace period of {shutdown_timeout:?} elapsed before \
+                         all connections drained; returning anyway."
+                    );
+                }
+            }
+        }
+        None => drain_all.await,
+    }
+}
@@ -0,0 +1,96 @@
+use std::net::SocketAddr;
+
+use super::ServerOptions;
+#[cfg(feature = "static-assets")]
+use super::static_assets::StaticAssets;
+
+/// The state shared across all the handlers of the HTMX-SSR server.
+pub struct ServerState {
+    /// The base URL of the server, as seen from the outside.
+    pub base_url: http::Uri,
+
+    /// Whether to trust `Forwarded`/`X-Forwarded-*` request headers to reconstruct the base URL
+    /// on a per-request basis. See [`BaseUrl`](super::BaseUrl).
+    trust_forwarded_headers: bool,
+
+    /// The mount path and registry of the server's static assets, if any were configured via
+    /// [`Server::with_static_assets`](super::Server::with_static_assets).
+    #[cfg(feature = "static-assets")]
+    static_assets: Option<(String, StaticAssets)>,
+}
+
+impl std::fmt::Debug for ServerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerState")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerState {
+    /// Instantiate a new server state from the given options and the local address the server is
+    /// listening on.
+    ///
+    /// If `options.base_url` is not set, the base URL is guessed from `local_addr`, using
+    /// `is_tls` to decide between the `http` and `https` schemes.
+    pub fn new(
+        options: ServerOptions,
+        local_addr: SocketAddr,
+        is_tls: bool,
+        #[cfg(feature = "static-assets")] static_assets: Option<(String, StaticAssets)>,
+    ) -> Self {
+        let base_url = options
+            .base_url
+            .unwrap_or_else(|| guess_base_url(local_addr, is_tls));
+
+        Self {
+            base_url,
+            trust_forwarded_headers: options.trust_forwarded_headers,
+            #[cfg(feature = "static-assets")]
+            static_assets,
+        }
+    }
+
+    /// Resolve the base URL to use for the current request.
+    ///
+    /// If [`trust_forwarded_headers`](ServerOptions::trust_forwarded_headers) is disabled, this
+    /// is always [`Self::base_url`]. Otherwise, it is reconstructed from the `Forwarded` header
+    /// (or, failing that, `X-Forwarded-Proto`/`X-Forwarded-Host`/`X-Forwarded-Port`), falling back
+    /// to [`Self::base_url`] if none of them are present or parseable.
+    pub(super) fn resolve_base_url(&self, headers: &http::HeaderMap) -> http::Uri {
+        if !self.trust_forwarded_headers {
+            return self.base_url.clone();
+        }
+
+        super::forwarded::base_url_from_headers(headers).unwrap_or_else(|| self.base_url.clone())
+    }
+
+    /// Get the registry of static assets mounted on this server, if any.
+    #[cfg(feature = "static-assets")]
+    pub(super) fn static_assets(&self) -> Option<&StaticAssets> {
+        self.static_assets
+            .as_ref()
+            .map(|(_, static_assets)| static_assets)
+    }
+
+    /// Build the path of a static asset under its mount path, using its fingerprinted name so the
+    /// result is safe to cache forever (e.g. `/assets/app.2f1a9c7b3d41.css`).
+    ///
+    /// Returns `None` if no static assets were configured, or if `logical_name` isn't one of
+    /// them.
+    #[cfg(feature = "static-assets")]
+    pub fn asset_path(&self, logical_name: &str) -> Option<String> {
+        let (mount_path, static_assets) = self.static_assets.as_ref()?;
+        let fingerprinted_name = static_assets.fingerprinted_name(logical_name)?;
+
+        Some(format!("{}/{fingerprinted_name}", mount_path.trim_end_matches('/')))
+    }
+}
+
+fn guess_base_url(local_addr: SocketAddr, is_tls: bool) -> http::Uri {
+    let scheme = if is_tls { "https" } else { "http" };
+
+    format!("{scheme}://{local_addr}")
+        .parse()
+        .expect("a socket address always produces a valid URI")
+}
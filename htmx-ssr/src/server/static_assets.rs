@@ -0,0 +1,371 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use axum::{
+    extract::{Path as PathParam, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use sha2::{Digest, Sha256};
+
+use super::{Router, ServerState};
+
+/// A single loaded static asset: its content plus the metadata needed to serve it with correct
+/// caching headers.
+struct Asset {
+    content: bytes::Bytes,
+    content_type: HeaderValue,
+    etag: HeaderValue,
+    last_modified: HeaderValue,
+}
+
+impl Asset {
+    fn load(name: &str, content: Vec<u8>, modified: std::time::SystemTime) -> (Self, String) {
+        let hash = format!("{:x}", Sha256::digest(&content));
+        let hash = hash[..12].to_owned();
+
+        let content_type = HeaderValue::from_str(content_type_for(name))
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
+        let etag = HeaderValue::from_str(&format!("\"{hash}\"")).expect("a valid ETag value");
+        let last_modified = HeaderValue::from_str(&httpdate::fmt_http_date(modified))
+            .expect("a valid Last-Modified value");
+
+        (
+            Self {
+                content: content.into(),
+                content_type,
+                etag,
+                last_modified,
+            },
+            hash,
+        )
+    }
+}
+
+fn content_type_for(name: &str) -> &'static str {
+    match name.rsplit('.').next().unwrap_or_default() {
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "html" => "text/html; charset=utf-8",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A registry of static assets mounted on a [`Server`](super::Server), computed once at startup.
+///
+/// Each asset is content-hashed when [`StaticAssets::from_dir`] runs, so templates can embed
+/// cache-busting URLs (via [`ServerState::asset_path`]) that are safe to cache forever: the
+/// fingerprinted name changes whenever the content does.
+pub struct StaticAssets {
+    /// Assets, keyed by their logical (unfingerprinted) name, e.g. `app.css`.
+    assets: HashMap<String, Asset>,
+
+    /// Maps a fingerprinted name, e.g. `app.2f1a9c7b3d41.css`, back to its logical name.
+    by_fingerprinted_name: HashMap<String, String>,
+
+    /// Maps a logical name to its fingerprinted name.
+    fingerprinted_names: HashMap<String, String>,
+}
+
+/// An error that can occur while loading static assets from a directory.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadStaticAssetsError {
+    /// An error occurred while reading the asset directory (or one of its subdirectories).
+    #[error("failed to read the asset directory at `{path}`: {err}")]
+    ReadDir {
+        /// The directory that failed to be read.
+        path: PathBuf,
+
+        /// The error that occurred.
+        #[source]
+        err: std::io::Error,
+    },
+
+    /// An error occurred while reading an asset file.
+    #[error("failed to read the asset file at `{path}`: {err}")]
+    ReadFile {
+        /// The file that failed to be read.
+        path: PathBuf,
+
+        /// The error that occurred.
+        #[source]
+        err: std::io::Error,
+    },
+}
+
+impl StaticAssets {
+    /// Recursively load every file under `dir` as a static asset.
+    ///
+    /// Asset names are the file paths relative to `dir`, with `/` as the separator regardless of
+    /// platform (so `dir/css/app.css` is served, and fingerprinted, as `css/app.css`).
+    pub async fn from_dir(dir: impl AsRef<Path>) -> Result<Self, LoadStaticAssetsError> {
+        let dir = dir.as_ref();
+
+        let mut assets = HashMap::new();
+        let mut by_fingerprinted_name = HashMap::new();
+        let mut fingerprinted_names = HashMap::new();
+        let mut pending = vec![dir.to_path_buf()];
+
+        while let Some(current) = pending.pop() {
+            let mut entries =
+                tokio::fs::read_dir(&current)
+                    .await
+                    .map_err(|err| LoadStaticAssetsError::ReadDir {
+                        path: current.clone(),
+                        err,
+                    })?;
+
+            while let Some(entry) =
+                entries
+                    .next_entry()
+                    .await
+                    .map_err(|err| LoadStaticAssetsError::ReadDir {
+                        path: current.clone(),
+                        err,
+                    })?
+            {
+                let path = entry.path();
+                let file_type =
+                    entry
+                        .file_type()
+                        .await
+                        .map_err(|err| LoadStaticAssetsError::ReadDir {
+                            path: path.clone(),
+                            err,
+                        })?;
+
+                if file_type.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+
+                let name = path
+                    .strip_prefix(dir)
+                    .expect("path was pushed from within dir")
+                    .to_string_lossy()
+                    .replace(std::path::MAIN_SEPARATOR, "/");
+
+                let content =
+                    tokio::fs::read(&path)
+                        .await
+                        .map_err(|err| LoadStaticAssetsError::ReadFile {
+                            path: path.clone(),
+                            err,
+                        })?;
+
+                let modified = entry
+                    .metadata()
+                    .await
+                    .and_then(|metadata| metadata.modified())
+                    .map_err(|err| LoadStaticAssetsError::ReadFile {
+                        path: path.clone(),
+                        err,
+                    })?;
+
+                let (asset, hash) = Asset::load(&name, content, modified);
+                let fingerprinted_name = match name.rsplit_once('.') {
+                    Some((stem, ext)) => format!("{stem}.{hash}.{ext}"),
+                    None => format!("{name}.{hash}"),
+                };
+
+                by_fingerprinted_name.insert(fingerprinted_name.clone(), name.clone());
+                fingerprinted_names.insert(name.clone(), fingerprinted_name);
+                assets.insert(name, asset);
+            }
+        }
+
+        Ok(Self {
+            assets,
+            by_fingerprinted_name,
+            fingerprinted_names,
+        })
+    }
+
+    /// Get the fingerprinted name of an asset given its logical name, e.g. `app.css` ->
+    /// `app.2f1a9c7b3d41.css`.
+    pub fn fingerprinted_name(&self, logical_name: &str) -> Option<&str> {
+        self.fingerprinted_names
+            .get(logical_name)
+            .map(String::as_str)
+    }
+
+    /// Resolve a requested name (either a logical or a fingerprinted one) to the asset to serve,
+    /// along with whether it should get `immutable` caching (true for fingerprinted names, which
+    /// can never change content).
+    fn resolve(&self, requested_name: &str) -> Option<(&Asset, bool)> {
+        if let Some(logical_name) = self.by_fingerprinted_name.get(requested_name) {
+            return self.assets.get(logical_name).map(|asset| (asset, true));
+        }
+
+        self.assets.get(requested_name).map(|asset| (asset, false))
+    }
+
+    /// Build the router serving the asset registry held by the [`ServerState`] it runs with
+    /// (the one set via [`Server::with_static_assets`](super::Server::with_static_assets)).
+    ///
+    /// Mount it under a prefix with [`axum::Router::nest`], e.g.
+    /// `router.nest("/assets", StaticAssets::router())`.
+    pub fn router() -> Router {
+        axum::Router::new().route("/{*name}", get(serve_asset))
+    }
+}
+
+async fn serve_asset(
+    State(state): State<std::sync::Arc<ServerState>>,
+    PathParam(name): PathParam<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(assets) = state.static_assets() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let Some((asset, immutable)) = assets.resolve(&name) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let cache_control = if immutable {
+        HeaderValue::from_static("public, max-age=31536000, immutable")
+    } else {
+        HeaderValue::from_static("public, max-age=3600, must-revalidate")
+    };
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value == asset.etag)
+    {
+        // RFC 7232 §4.1: a 304 should carry the same validators/freshness headers the 200 would,
+        // so caches can refresh their freshness lifetime on revalidation.
+        return (
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, asset.etag.clone()),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+        )
+            .into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, asset.content_type.clone()),
+            (header::ETAG, asset.etag.clone()),
+            (header::LAST_MODIFIED, asset.last_modified.clone()),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        asset.content.clone(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str, content: &[u8]) -> (String, Asset, String) {
+        let (asset, hash) = Asset::load(name, content.to_vec(), std::time::SystemTime::UNIX_EPOCH);
+        (name.to_owned(), asset, hash)
+    }
+
+    fn static_assets(entries: &[(&str, &[u8])]) -> StaticAssets {
+        let mut assets = HashMap::new();
+        let mut by_fingerprinted_name = HashMap::new();
+        let mut fingerprinted_names = HashMap::new();
+
+        for (name, content) in entries {
+            let (name, asset, hash) = asset(name, content);
+            let fingerprinted_name = match name.rsplit_once('.') {
+                Some((stem, ext)) => format!("{stem}.{hash}.{ext}"),
+                None => format!("{name}.{hash}"),
+            };
+
+            by_fingerprinted_name.insert(fingerprinted_name.clone(), name.clone());
+            fingerprinted_names.insert(name.clone(), fingerprinted_name);
+            assets.insert(name, asset);
+        }
+
+        StaticAssets {
+            assets,
+            by_fingerprinted_name,
+            fingerprinted_names,
+        }
+    }
+
+    #[test]
+    fn fingerprinted_name_inserts_the_hash_before_the_extension() {
+        let assets = static_assets(&[("app.css", b"body {}")]);
+
+        let fingerprinted_name = assets.fingerprinted_name("app.css").unwrap();
+
+        assert!(fingerprinted_name.starts_with("app."));
+        assert!(fingerprinted_name.ends_with(".css"));
+        assert_ne!(fingerprinted_name, "app.css");
+    }
+
+    #[test]
+    fn fingerprinted_name_handles_extension_less_files() {
+        let assets = static_assets(&[("favicon", b"not-actually-an-icon")]);
+
+        let fingerprinted_name = assets.fingerprinted_name("favicon").unwrap();
+
+        assert!(fingerprinted_name.starts_with("favicon."));
+        assert_ne!(fingerprinted_name, "favicon");
+    }
+
+    #[test]
+    fn fingerprinted_name_is_none_for_an_unknown_asset() {
+        let assets = static_assets(&[("app.css", b"body {}")]);
+
+        assert!(assets.fingerprinted_name("missing.css").is_none());
+    }
+
+    #[test]
+    fn resolve_by_logical_name_is_not_immutable() {
+        let assets = static_assets(&[("app.css", b"body {}")]);
+
+        let (_, immutable) = assets.resolve("app.css").unwrap();
+
+        assert!(!immutable);
+    }
+
+    #[test]
+    fn resolve_by_fingerprinted_name_is_immutable() {
+        let assets = static_assets(&[("app.css", b"body {}")]);
+        let fingerprinted_name = assets.fingerprinted_name("app.css").unwrap().to_owned();
+
+        let (_, immutable) = assets.resolve(&fingerprinted_name).unwrap();
+
+        assert!(immutable);
+    }
+
+    #[test]
+    fn resolve_returns_none_for_an_unknown_name() {
+        let assets = static_assets(&[("app.css", b"body {}")]);
+
+        assert!(assets.resolve("missing.css").is_none());
+    }
+
+    #[test]
+    fn resolve_round_trips_extension_less_files() {
+        let assets = static_assets(&[("favicon", b"not-actually-an-icon")]);
+        let fingerprinted_name = assets.fingerprinted_name("favicon").unwrap().to_owned();
+
+        let (logical, logical_immutable) = assets.resolve("favicon").unwrap();
+        let (fingerprinted, fingerprinted_immutable) = assets.resolve(&fingerprinted_name).unwrap();
+
+        assert_eq!(logical.content, fingerprinted.content);
+        assert!(!logical_immutable);
+        assert!(fingerprinted_immutable);
+    }
+}
@@ -0,0 +1,191 @@
+use std::{io, net::SocketAddr, path::Path, sync::Arc};
+
+use tokio::net::TcpListener;
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer},
+        ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+
+/// A TLS configuration for the server, backed by `rustls`.
+///
+/// Set it on a [`Server`](super::Server) via
+/// [`with_rustls_config`](super::Server::with_rustls_config) to terminate TLS in-process, without
+/// needing a reverse proxy in front of the server.
+#[derive(Clone)]
+pub struct RustlsConfig {
+    acceptor: TlsAcceptor,
+}
+
+impl std::fmt::Debug for RustlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RustlsConfig").finish_non_exhaustive()
+    }
+}
+
+/// An error that can occur when loading a [`RustlsConfig`] from PEM files.
+#[derive(Debug, thiserror::Error)]
+pub enum RustlsConfigFromPemFileError {
+    /// An error occurred while reading the certificate file.
+    #[error("failed to read the certificate file at `{path}`: {err}")]
+    ReadCertificate {
+        /// The path that failed to be read.
+        path: std::path::PathBuf,
+
+        /// The error that occurred.
+        #[source]
+        err: std::io::Error,
+    },
+
+    /// An error occurred while reading the private key file.
+    #[error("failed to read the private key file at `{path}`: {err}")]
+    ReadPrivateKey {
+        /// The path that failed to be read.
+        path: std::path::PathBuf,
+
+        /// The error that occurred.
+        #[source]
+        err: std::io::Error,
+    },
+
+    /// No private key was found in the private key file.
+    #[error("no private key found in `{path}`")]
+    MissingPrivateKey {
+        /// The path that was expected to contain a private key.
+        path: std::path::PathBuf,
+    },
+
+    /// An error occurred while building the rustls server configuration.
+    #[error("failed to build the rustls server configuration: {0}")]
+    Config(#[from] tokio_rustls::rustls::Error),
+}
+
+impl RustlsConfig {
+    /// Load a new TLS configuration from a PEM-encoded certificate chain file and a PEM-encoded
+    /// private key file.
+    pub async fn from_pem_file(
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> Result<Self, RustlsConfigFromPemFileError> {
+        let cert_path = cert_path.as_ref();
+        let key_path = key_path.as_ref();
+
+        let certs = load_certs(cert_path).await?;
+        let key = load_key(key_path).await?;
+
+        // Pin the crypto provider explicitly rather than relying on `ServerConfig::builder()`'s
+        // process-default `CryptoProvider`, which panics at the first handshake unless the
+        // embedding application happened to install one first.
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("the ring provider supports rustls' default protocol versions")
+            .with_no_client_auth()
+            .with_single_cert(certs, key)?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    /// Get a `tokio_rustls` acceptor for this configuration.
+    pub(crate) fn acceptor(&self) -> TlsAcceptor {
+        self.acceptor.clone()
+    }
+}
+
+/// A TCP listener that terminates TLS on every accepted connection, using a [`RustlsConfig`].
+///
+/// Implements `axum::serve::Listener`, so it can be handed to `axum::serve` just like a plain
+/// `tokio::net::TcpListener`.
+pub(crate) struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    pub(crate) fn new(listener: TcpListener, config: &RustlsConfig) -> Self {
+        Self {
+            listener,
+            acceptor: config.acceptor(),
+        }
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<tokio::net::TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (stream, addr) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    // Mirrors `axum`'s own `TcpListener` implementation: a failed `accept` is
+                    // almost always transient (e.g. too many open files), so we log and retry
+                    // rather than taking the whole server down.
+                    handle_accept_error(err).await;
+                    continue;
+                }
+            };
+
+            match self.acceptor.accept(stream).await {
+                Ok(stream) => return (stream, addr),
+                Err(err) => {
+                    tracing::warn!("TLS handshake with {addr} failed: {err}");
+                    continue;
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+async fn handle_accept_error(err: io::Error) {
+    tracing::warn!("Failed to accept a TCP connection: {err}");
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+async fn load_certs(
+    path: &Path,
+) -> Result<Vec<CertificateDer<'static>>, RustlsConfigFromPemFileError> {
+    let bytes =
+        tokio::fs::read(path)
+            .await
+            .map_err(|err| RustlsConfigFromPemFileError::ReadCertificate {
+                path: path.to_owned(),
+                err,
+            })?;
+
+    rustls_pemfile::certs(&mut bytes.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| RustlsConfigFromPemFileError::ReadCertificate {
+            path: path.to_owned(),
+            err,
+        })
+}
+
+async fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, RustlsConfigFromPemFileError> {
+    let bytes =
+        tokio::fs::read(path)
+            .await
+            .map_err(|err| RustlsConfigFromPemFileError::ReadPrivateKey {
+                path: path.to_owned(),
+                err,
+            })?;
+
+    rustls_pemfile::private_key(&mut bytes.as_slice())
+        .map_err(|err| RustlsConfigFromPemFileError::ReadPrivateKey {
+            path: path.to_owned(),
+            err,
+        })?
+        .ok_or_else(|| RustlsConfigFromPemFileError::MissingPrivateKey {
+            path: path.to_owned(),
+        })
+}